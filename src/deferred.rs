@@ -0,0 +1,92 @@
+//! Deferred props: prop values excluded from the initial page load and
+//! fetched afterwards via an automatic partial reload, so expensive
+//! data doesn't hold up the first render.
+//!
+//! See more at: <https://inertiajs.com/partial-reloads#deferred-props>
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A prop whose value is resolved lazily.
+///
+/// Put one as a top-level value in the props passed to
+/// [crate::Inertia::render], or in a shared prop registered via
+/// [crate::Inertia::share] (e.g. `json!({"stats": Deferred::new(...)})`);
+/// it keeps its value out of the initial document, and resolves it
+/// only once the client follows up with a partial reload naming it.
+/// `render` only looks for the marker a `Deferred` serializes itself
+/// to among the top-level keys of the merged props, so nesting one
+/// inside a child object or array isn't supported — it's never
+/// grouped into `deferredProps`, and comes back as `null` instead of
+/// its real value.
+pub struct Deferred {
+    pub(crate) group: &'static str,
+    pub(crate) resolve: Arc<dyn Fn() -> Value + Send + Sync>,
+}
+
+impl Deferred {
+    /// `group` names the batch this prop is fetched alongside other
+    /// deferred props sharing the same group. `resolve` computes the
+    /// real value; it only runs once the client asks for this prop by
+    /// name in a follow-up partial reload.
+    pub fn new(group: &'static str, resolve: impl Fn() -> Value + Send + Sync + 'static) -> Self {
+        Deferred {
+            group,
+            resolve: Arc::new(resolve),
+        }
+    }
+}
+
+/// The JSON key a [Deferred] serializes itself under so
+/// `Inertia::render` can find it back in the serialized props object.
+pub(crate) const MARKER_KEY: &str = "$__axum_inertia_deferred_id";
+
+/// A registered [Deferred]'s group and resolver, as stashed by
+/// [Deferred]'s `Serialize` impl and looked up by id via [take].
+type PendingEntry = (&'static str, Arc<dyn Fn() -> Value + Send + Sync>);
+
+/// Resolvers registered by [Deferred]'s `Serialize` impl, keyed by the
+/// id carried on their marker so a later lookup doesn't depend on
+/// running on the same thread that registered it.
+fn registry() -> &'static Mutex<HashMap<u64, PendingEntry>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<u64, PendingEntry>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+impl Serialize for Deferred {
+    /// Doesn't serialize the real value (that's the whole point):
+    /// stashes the resolver in the registry under a fresh id and
+    /// writes out a marker object carrying that id, so
+    /// `serde_json::to_value` can still be used on props that contain
+    /// deferred entries.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        registry()
+            .lock()
+            .unwrap()
+            .insert(id, (self.group, self.resolve.clone()));
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(MARKER_KEY, &id)?;
+        map.end()
+    }
+}
+
+/// Looks up and removes the [Deferred] registered under `id`. Returns
+/// `None` if nothing is registered under `id` (e.g. it was already
+/// taken), which callers treat as "drop the marker" rather than
+/// resolving it.
+pub(crate) fn take(id: u64) -> Option<PendingEntry> {
+    registry().lock().unwrap().remove(&id)
+}
+
+/// Test-only probe for the registry's size, used to assert that a
+/// [Deferred] never outlives the request that registered it.
+#[cfg(test)]
+pub(crate) fn registry_len() -> usize {
+    registry().lock().unwrap().len()
+}
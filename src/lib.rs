@@ -13,7 +13,7 @@
 //! use serde_json::json;
 //!
 //! async fn get_posts(i: Inertia) -> impl IntoResponse {
-//!     i.render("Posts/Index", json!({ "posts": vec!["post one", "post two"] }))
+//!     i.render("Posts/Index", json!({ "posts": vec!["post one", "post two"] })).await
 //! }
 //! ```
 //! [Extractor]: https://docs.rs/axum/latest/axum/#extractors
@@ -50,20 +50,52 @@ use page::Page;
 use request::Request;
 use response::Response;
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
+pub use deferred::Deferred;
+
+mod deferred;
 mod page;
 mod request;
 mod response;
 pub mod vite;
 
+/// The future returned by a [LayoutResolver].
+pub(crate) type LayoutFuture = Pin<Box<dyn Future<Output = String> + Send>>;
+
+/// A function from the serialized `Page` JSON and a per-request CSP
+/// nonce to the initial page load html. Boxed as a future since
+/// building the document may require I/O, e.g. a round trip to an
+/// SSR server (see [crate::vite::Ssr]).
+pub(crate) type LayoutResolver = Box<dyn Fn(String, String) -> LayoutFuture + Send + Sync>;
+
+type SharedPropsFn = dyn Fn(&Parts) -> Value + Send + Sync;
+
+/// A `Deferred` found among a shared prop's values: the key it was
+/// registered under, its group, and its resolver. Taken out of
+/// [deferred]'s registry as soon as `from_request_parts` sees it, so
+/// its lifetime is tied to `Inertia` rather than to `render` running.
+type SharedDeferred = (String, &'static str, Arc<dyn Fn() -> Value + Send + Sync>);
+
 #[derive(Clone)]
 pub struct Inertia {
     request: Option<Request>,
     version: Option<String>,
-    /// A function from the serialized page props to the initial page
-    /// load html.
-    layout: Arc<Box<dyn Fn(String) -> String + Send + Sync>>,
+    layout: Arc<LayoutResolver>,
+    shared_props: Vec<Arc<SharedPropsFn>>,
+    /// The shared props evaluated and deep-merged for the current
+    /// request, with any `Deferred` values already pulled out into
+    /// `shared_deferred`. Populated in `from_request_parts`, since
+    /// that's where the request's `Parts` are available; empty until
+    /// then.
+    shared: Value,
+    /// `Deferred` props found among `shared`'s values; see
+    /// [SharedDeferred].
+    shared_deferred: Vec<SharedDeferred>,
 }
 
 #[async_trait]
@@ -91,6 +123,37 @@ where
             return Err((StatusCode::CONFLICT, headers));
         }
 
+        // Pull any `Deferred` out of `shared` after each `.share()`
+        // closure, rather than once at the end, so a later closure
+        // shadowing an earlier one's key can't leak that key's
+        // registry entry.
+        let mut shared = Value::Object(serde_json::Map::new());
+        let mut shared_deferred: Vec<SharedDeferred> = Vec::new();
+        for f in &inertia.shared_props {
+            deep_merge(&mut shared, f(parts));
+
+            if let Value::Object(map) = &mut shared {
+                // A plain value set by this closure shadows any marker
+                // an earlier closure stashed under the same key — the
+                // marker's own key no longer exists in `map` by the
+                // time this runs (the `remove` below already took it
+                // out), so `deep_merge` just inserted the plain value
+                // afresh; drop the stale `shared_deferred` entry so
+                // `render` doesn't treat that key as deferred.
+                shared_deferred.retain(|(key, _, _)| !map.contains_key(key));
+
+                for (key, id) in find_deferred_markers(map) {
+                    if let Some((group, resolve)) = deferred::take(id) {
+                        map.remove(&key);
+                        shared_deferred.push((key, group, resolve));
+                    }
+                }
+            }
+        }
+
+        inertia.shared = shared;
+        inertia.shared_deferred = shared_deferred;
+
         inertia.request = Some(request);
         Ok(inertia)
     }
@@ -102,36 +165,273 @@ impl Inertia {
     /// `layout` provides information about how to render the initial
     /// page load. See the [crate::vite] module for an implementation
     /// of this for vite.
-    pub fn new(
-        version: Option<String>,
-        layout: Box<dyn Fn(String) -> String + Send + Sync>,
-    ) -> Inertia {
+    pub fn new(version: Option<String>, layout: LayoutResolver) -> Inertia {
         Inertia {
             request: None,
             version,
             layout: Arc::new(layout),
+            shared_props: Vec::new(),
+            shared: Value::Object(serde_json::Map::new()),
+            shared_deferred: Vec::new(),
         }
     }
 
+    /// Registers a shared prop, evaluated fresh on every request and
+    /// deep-merged under each render's component props (which win on
+    /// key conflicts). `f` receives the request's `Parts` so shared
+    /// props can depend on request state like headers or cookies —
+    /// e.g. the authenticated user or flash messages.
+    ///
+    /// A top-level [Deferred] is allowed here too, with the same
+    /// deferred/partial-reload handling as one returned from the
+    /// component's own props — except that if `render`'s own props
+    /// set the same key, the shared [Deferred] is dropped rather than
+    /// resolved or grouped, since the component's value wins there
+    /// too.
+    pub fn share(mut self, f: impl Fn(&Parts) -> Value + Send + Sync + 'static) -> Inertia {
+        self.shared_props.push(Arc::new(f));
+        self
+    }
+
     /// Renders an Inertia response.
-    pub fn render<S: Serialize>(self, component: &'static str, props: S) -> Response {
+    ///
+    /// On a partial reload (an Inertia XHR request whose
+    /// `X-Inertia-Partial-Component` matches `component`), only the
+    /// keys named in `X-Inertia-Partial-Data` are kept (or, if
+    /// `X-Inertia-Partial-Except` is set instead, every key but
+    /// those). A partial-data header naming a different component is
+    /// ignored and the full prop set is returned, since the client
+    /// has navigated away from the component it was requested for.
+    ///
+    /// Building the initial document for a non-XHR request may
+    /// require I/O (e.g. a round trip to an SSR server, see
+    /// [crate::vite::Ssr]), so this is async.
+    ///
+    /// A fresh CSP nonce is minted for every non-XHR render and
+    /// passed to the layout so it can stamp it onto the `<script>`
+    /// and `<link>` tags it emits; the same nonce is set on the
+    /// response's `Content-Security-Policy` header.
+    ///
+    /// [Deferred] props found in `props` — or in a shared prop
+    /// registered via [Inertia::share] — are pulled out and excluded
+    /// from the payload, unless this is a partial reload that
+    /// explicitly requested them via `X-Inertia-Partial-Data`, in
+    /// which case they're resolved and included like any other prop.
+    /// Only a non-partial request groups the excluded ones by name
+    /// under `deferredProps`; a partial reload that doesn't request
+    /// one simply drops it.
+    ///
+    /// Shared props registered via [Inertia::share] are deep-merged
+    /// underneath `props`, which wins on key conflicts, and take part
+    /// in the same partial-reload filtering as `props` — a shared key
+    /// not named in a partial reload is dropped just like a
+    /// component-specific one would be. If `props` sets the same key
+    /// as a shared [Deferred], the shared one is dropped outright
+    /// (its resolver is never called) rather than appearing in
+    /// `deferredProps`, since `props` shadows it either way.
+    pub async fn render<S: Serialize>(self, component: &'static str, props: S) -> Response {
         let request = self.request.expect("no request set");
         let url = request.url.clone();
+        let component_props = serde_json::to_value(props).expect("serialize");
+
+        let is_partial_reload = request.is_xhr
+            && request
+                .partial_component
+                .as_deref()
+                .is_some_and(|c| c == component);
+
+        // Snapshotted before `component_props` moves into `deep_merge`
+        // below, so the shared-deferred loop can still tell which keys
+        // `render`'s own props set.
+        let component_keys: std::collections::HashSet<String> = match &component_props {
+            Value::Object(map) => map.keys().cloned().collect(),
+            _ => Default::default(),
+        };
+
+        let mut props = self.shared.clone();
+        deep_merge(&mut props, component_props);
+
+        let mut deferred_props: HashMap<&'static str, Vec<String>> = HashMap::new();
+        if let Value::Object(map) = &mut props {
+            for (key, id) in find_deferred_markers(map) {
+                let Some((group, resolve)) = deferred::take(id) else {
+                    map.remove(&key);
+                    continue;
+                };
+                apply_deferred(
+                    map,
+                    &mut deferred_props,
+                    is_partial_reload,
+                    request.partial_data.as_ref(),
+                    key,
+                    group,
+                    &resolve,
+                );
+            }
+
+            // A shared `Deferred` shadowed by `render`'s own props is
+            // skipped; its resolver was already taken out of the
+            // registry back in `from_request_parts`, so there's
+            // nothing to leak by dropping it here.
+            for (key, group, resolve) in &self.shared_deferred {
+                if component_keys.contains(key.as_str()) {
+                    continue;
+                }
+                apply_deferred(
+                    map,
+                    &mut deferred_props,
+                    is_partial_reload,
+                    request.partial_data.as_ref(),
+                    key.clone(),
+                    group,
+                    resolve,
+                );
+            }
+
+            // A nested `Deferred` (unsupported, see its docs) never
+            // gets picked up above; strip it here instead of leaking
+            // its registry entry.
+            for value in map.values_mut() {
+                reclaim_nested_deferred_markers(value);
+            }
+        }
+
+        if is_partial_reload {
+            if let Value::Object(map) = &mut props {
+                if let Some(only) = &request.partial_data {
+                    map.retain(|key, _| only.iter().any(|k| k == key));
+                } else if let Some(except) = &request.partial_except {
+                    map.retain(|key, _| !except.iter().any(|k| k == key));
+                }
+            }
+        }
+
         let page = Page {
             component,
-            props: serde_json::to_value(props).expect("serialize"),
+            props,
             url,
             version: self.version.clone(),
+            deferred_props: (!deferred_props.is_empty()).then_some(deferred_props),
+        };
+
+        let (document, nonce) = if request.is_xhr {
+            (None, None)
+        } else {
+            let nonce = generate_nonce();
+            let props = serde_json::to_string(&page).expect("serialize page");
+            let document = (self.layout)(props, nonce.clone()).await;
+            (Some(document), Some(nonce))
         };
+
         Response {
             page,
-            request,
-            layout: self.layout,
+            is_xhr: request.is_xhr,
+            document,
+            nonce,
             version: self.version,
         }
     }
 }
 
+/// Generates a fresh per-request CSP nonce: 16 random bytes,
+/// base64-encoded.
+fn generate_nonce() -> String {
+    use base64::Engine;
+    use rand::RngCore;
+
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Deep-merges `overlay` into `base`: object keys are merged
+/// recursively, with `overlay`'s value winning on conflict; any other
+/// value (including a type mismatch, e.g. an object overlaid with a
+/// string) simply replaces `base` outright.
+fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => {
+            *base = overlay;
+        }
+    }
+}
+
+/// Resolves or groups one [Deferred] entry found at `key`: on a
+/// partial reload that names `key` in `X-Inertia-Partial-Data`,
+/// inserts its resolved value into `map`; otherwise removes `key` from
+/// `map` and, on a non-partial initial request, records it under
+/// `group` in `deferred_props` so it's reported in `deferredProps`.
+fn apply_deferred(
+    map: &mut serde_json::Map<String, Value>,
+    deferred_props: &mut HashMap<&'static str, Vec<String>>,
+    is_partial_reload: bool,
+    partial_data: Option<&Vec<String>>,
+    key: String,
+    group: &'static str,
+    resolve: &Arc<dyn Fn() -> Value + Send + Sync>,
+) {
+    let requested =
+        is_partial_reload && partial_data.is_some_and(|only| only.iter().any(|k| k == &key));
+    if requested {
+        map.insert(key, (resolve.as_ref())());
+    } else {
+        map.remove(&key);
+        if !is_partial_reload {
+            deferred_props.entry(group).or_default().push(key);
+        }
+    }
+}
+
+/// Finds every top-level key in `map` whose value is the marker a
+/// [Deferred] serializes itself to, paired with the id on that marker.
+fn find_deferred_markers(map: &serde_json::Map<String, Value>) -> Vec<(String, u64)> {
+    map.iter()
+        .filter_map(|(key, value)| {
+            value
+                .get(deferred::MARKER_KEY)
+                .and_then(Value::as_u64)
+                .map(|id| (key.clone(), id))
+        })
+        .collect()
+}
+
+/// Finds `Deferred` markers nested below a top-level prop (so
+/// [find_deferred_markers] never saw them), reclaims their registry
+/// slot, and blanks each one to `null` so the internal marker object
+/// never reaches the client.
+fn reclaim_nested_deferred_markers(value: &mut Value) {
+    if let Value::Object(map) = &*value {
+        if let Some(id) = map.get(deferred::MARKER_KEY).and_then(Value::as_u64) {
+            deferred::take(id);
+            *value = Value::Null;
+            return;
+        }
+    }
+    match value {
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                reclaim_nested_deferred_markers(value);
+            }
+        }
+        Value::Array(items) => {
+            for value in items.iter_mut() {
+                reclaim_nested_deferred_markers(value);
+            }
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,11 +443,14 @@ mod tests {
     #[tokio::test]
     async fn it_works() {
         async fn handler(i: Inertia) -> impl IntoResponse {
-            i.render("foo!", json!({"bar": "baz"}))
+            i.render("foo!", json!({"bar": "baz"})).await
         }
 
-        let layout =
-            Box::new(|props| format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props));
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
 
         let inertia = Inertia::new(Some("123".to_string()), layout);
 
@@ -180,11 +483,14 @@ mod tests {
     #[tokio::test]
     async fn it_responds_with_conflict_on_version_mismatch() {
         async fn handler(i: Inertia) -> impl IntoResponse {
-            i.render("foo!", json!({"bar": "baz"}))
+            i.render("foo!", json!({"bar": "baz"})).await
         }
 
-        let layout =
-            Box::new(|props| format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props));
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
 
         let inertia = Inertia::new(Some("123".to_string()), layout);
 
@@ -220,4 +526,649 @@ mod tests {
             Some("/test")
         );
     }
+
+    #[tokio::test]
+    async fn it_filters_props_on_matching_partial_reload() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz", "quux": "quuz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .header("X-Inertia-Partial-Component", "foo!")
+            .header("X-Inertia-Partial-Data", "bar")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"bar": "baz"}));
+    }
+
+    #[tokio::test]
+    async fn it_filters_props_on_matching_partial_reload_except() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz", "quux": "quuz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .header("X-Inertia-Partial-Component", "foo!")
+            .header("X-Inertia-Partial-Except", "bar")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"quux": "quuz"}));
+    }
+
+    #[tokio::test]
+    async fn it_ignores_partial_reload_for_a_different_component() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz", "quux": "quuz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .header("X-Inertia-Partial-Component", "bar!")
+            .header("X-Inertia-Partial-Data", "bar")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"bar": "baz", "quux": "quuz"}));
+    }
+
+    #[tokio::test]
+    async fn it_sets_a_csp_header_matching_the_document_nonce() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, nonce| {
+            Box::pin(async move {
+                format!(
+                    r#"<html><body><script nonce="{nonce}"></script><div id="app" data-page='{}'></div>"#,
+                    props
+                )
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let res = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap();
+
+        let csp = res
+            .headers()
+            .get("Content-Security-Policy")
+            .map(|h| h.to_str().unwrap())
+            .unwrap()
+            .to_string();
+        let nonce = csp
+            .strip_prefix("script-src 'nonce-")
+            .and_then(|s| s.strip_suffix("'"))
+            .unwrap()
+            .to_string();
+
+        let body = res.text().await.unwrap();
+        assert!(body.contains(&format!(r#"nonce="{nonce}""#)));
+    }
+
+    #[tokio::test]
+    async fn it_omits_deferred_props_from_the_initial_load() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render(
+                "foo!",
+                json!({
+                    "bar": "baz",
+                    "stats": Deferred::new("default", || json!({"visits": 42})),
+                }),
+            )
+            .await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"bar": "baz"}));
+        assert_eq!(body["deferredProps"], json!({"default": ["stats"]}));
+    }
+
+    #[tokio::test]
+    async fn it_resolves_deferred_props_on_a_matching_partial_reload() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render(
+                "foo!",
+                json!({
+                    "bar": "baz",
+                    "stats": Deferred::new("default", || json!({"visits": 42})),
+                }),
+            )
+            .await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .header("X-Inertia-Partial-Component", "foo!")
+            .header("X-Inertia-Partial-Data", "stats")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"stats": {"visits": 42}}));
+        assert!(body.get("deferredProps").is_none());
+    }
+
+    #[tokio::test]
+    async fn it_merges_shared_props_under_component_props() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout).share(|_parts| json!({"user": "alice", "bar": "shared"}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"user": "alice", "bar": "baz"}));
+    }
+
+    #[tokio::test]
+    async fn it_drops_unrequested_shared_props_on_partial_reload() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout).share(|_parts| json!({"user": "alice"}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .header("X-Inertia-Partial-Component", "foo!")
+            .header("X-Inertia-Partial-Data", "bar")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"bar": "baz"}));
+    }
+
+    #[tokio::test]
+    async fn it_resolves_a_deferred_prop_registered_via_share() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout)
+            .share(|_parts| json!({"stats": Deferred::new("default", || json!({"visits": 42}))}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        // Initial load: the shared `Deferred` is grouped, not resolved.
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"bar": "baz"}));
+        assert_eq!(body["deferredProps"], json!({"default": ["stats"]}));
+
+        // Partial reload naming it: it's resolved like any other prop.
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .header("X-Inertia-Partial-Component", "foo!")
+            .header("X-Inertia-Partial-Data", "stats")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"stats": {"visits": 42}}));
+        assert!(body.get("deferredProps").is_none());
+    }
+
+    #[tokio::test]
+    async fn it_drops_a_shared_deferred_prop_shadowed_by_a_render_prop() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"stats": "real"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout)
+            .share(|_parts| json!({"stats": Deferred::new("default", || json!({"visits": 42}))}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"stats": "real"}));
+        assert!(body.get("deferredProps").is_none());
+        assert_eq!(super::deferred::registry_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_drops_a_shared_deferred_prop_shadowed_by_a_render_deferred_prop() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render(
+                "foo!",
+                json!({"stats": Deferred::new("component-group", || json!({"visits": 7}))}),
+            )
+            .await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout)
+            .share(|_parts| json!({"stats": Deferred::new("shared-group", || json!({"visits": 42}))}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({}));
+        assert_eq!(
+            body["deferredProps"],
+            json!({"component-group": ["stats"]})
+        );
+        assert_eq!(super::deferred::registry_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_leak_a_shared_deferred_prop_shadowed_by_a_later_shared_prop() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout)
+            .share(|_parts| json!({"stats": Deferred::new("default", || json!({"visits": 42}))}))
+            .share(|_parts| json!({"stats": "overwritten"}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"bar": "baz", "stats": "overwritten"}));
+        assert!(body.get("deferredProps").is_none());
+        assert_eq!(super::deferred::registry_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_leak_a_shared_deferred_prop_when_render_is_never_called() {
+        async fn handler(_i: Inertia) -> impl IntoResponse {
+            "ignored"
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout)
+            .share(|_parts| json!({"stats": Deferred::new("default", || json!({"visits": 42}))}));
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let res = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(super::deferred::registry_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_leak_a_deferred_prop_nested_under_a_render_prop() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render(
+                "foo!",
+                json!({"outer": {"stats": Deferred::new("default", || json!({"visits": 42}))}}),
+            )
+            .await
+        }
+
+        let layout: LayoutResolver = Box::new(|props, _nonce| {
+            Box::pin(async move {
+                format!(r#"<html><body><div id="app" data-page='{}'></div>"#, props)
+            })
+        });
+
+        let inertia = Inertia::new(None, layout);
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(inertia);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let server = Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service());
+            server.await.expect("server error");
+        });
+
+        let client = reqwest::Client::new();
+
+        let res = client
+            .get(format!("http://{}/test", &addr))
+            .header("X-Inertia", "true")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["props"], json!({"outer": {"stats": null}}));
+        assert_eq!(super::deferred::registry_len(), 0);
+    }
 }
@@ -0,0 +1,20 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The `Page` object sent to the client, either serialized directly
+/// as JSON (for Inertia XHR requests) or embedded in the initial
+/// document's `data-page` attribute.
+///
+/// See more at: <https://inertiajs.com/the-protocol#the-page-object>
+#[derive(Serialize)]
+pub struct Page {
+    pub component: &'static str,
+    pub props: Value,
+    pub url: String,
+    pub version: Option<String>,
+    /// Deferred prop names, grouped by their group name. Omitted from
+    /// the serialized page entirely when there are none.
+    #[serde(rename = "deferredProps", skip_serializing_if = "Option::is_none")]
+    pub deferred_props: Option<HashMap<&'static str, Vec<String>>>,
+}
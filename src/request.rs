@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use http::{request::Parts, HeaderMap, HeaderValue, StatusCode};
+
+/// Information gleaned from an inbound request's Inertia headers.
+///
+/// See more at: <https://inertiajs.com/the-protocol>
+#[derive(Clone, Debug, Default)]
+pub struct Request {
+    /// Whether this is an Inertia XHR request, i.e. whether the
+    /// `X-Inertia` header was set to `true`.
+    pub is_xhr: bool,
+    /// The asset version the client currently has loaded, from the
+    /// `X-Inertia-Version` header.
+    pub version: Option<String>,
+    /// The request path, used to populate `Page::url`.
+    pub url: String,
+    /// The component named in `X-Inertia-Partial-Component`. A
+    /// partial reload only applies when this matches the component
+    /// being rendered.
+    pub partial_component: Option<String>,
+    /// The keys requested via `X-Inertia-Partial-Data`, parsed from
+    /// its comma-separated value.
+    pub partial_data: Option<Vec<String>>,
+    /// The keys to exclude via `X-Inertia-Partial-Except`, parsed
+    /// from its comma-separated value.
+    pub partial_except: Option<Vec<String>>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Request
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, HeaderMap<HeaderValue>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let is_xhr = header_str(parts, "X-Inertia") == Some("true");
+        let version = header_str(parts, "X-Inertia-Version").map(str::to_string);
+        let url = parts.uri.path().to_string();
+        let partial_component = header_str(parts, "X-Inertia-Partial-Component").map(str::to_string);
+        let partial_data = header_str(parts, "X-Inertia-Partial-Data").map(split_csv);
+        let partial_except = header_str(parts, "X-Inertia-Partial-Except").map(split_csv);
+
+        Ok(Request {
+            is_xhr,
+            version,
+            url,
+            partial_component,
+            partial_data,
+            partial_except,
+        })
+    }
+}
+
+fn header_str<'a>(parts: &'a Parts, name: &str) -> Option<&'a str> {
+    parts.headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn split_csv(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
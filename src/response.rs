@@ -0,0 +1,58 @@
+use crate::page::Page;
+use axum::response::{Html, IntoResponse, Response as AxumResponse};
+use http::HeaderValue;
+
+/// The response returned from [crate::Inertia::render].
+pub struct Response {
+    pub(crate) page: Page,
+    pub(crate) is_xhr: bool,
+    /// The fully-built initial document, precomputed in `render`
+    /// since producing it may require I/O. `None` for XHR requests,
+    /// which only need the serialized page.
+    pub(crate) document: Option<String>,
+    /// The CSP nonce stamped onto the document's `<script>`/`<link>`
+    /// tags, mirrored into the `Content-Security-Policy` header.
+    /// `None` for XHR requests, which have no document to protect.
+    pub(crate) nonce: Option<String>,
+    pub(crate) version: Option<String>,
+}
+
+impl IntoResponse for Response {
+    fn into_response(self) -> AxumResponse {
+        let mut response = if self.is_xhr {
+            let body = serde_json::to_string(&self.page).expect("serialize page");
+            let mut response = body.into_response();
+            response
+                .headers_mut()
+                .insert("X-Inertia", HeaderValue::from_static("true"));
+            response
+                .headers_mut()
+                .insert("Vary", HeaderValue::from_static("X-Inertia"));
+            response.headers_mut().insert(
+                "Content-Type",
+                HeaderValue::from_static("application/json"),
+            );
+            response
+        } else {
+            let document = self.document.expect("document built for non-xhr request");
+            let mut response = Html(document).into_response();
+            if let Some(nonce) = &self.nonce {
+                response.headers_mut().insert(
+                    "Content-Security-Policy",
+                    HeaderValue::from_str(&format!("script-src 'nonce-{nonce}'"))
+                        .expect("valid nonce header"),
+                );
+            }
+            response
+        };
+
+        if let Some(version) = &self.version {
+            response.headers_mut().insert(
+                "X-Inertia-Version",
+                HeaderValue::from_str(version).expect("valid version header"),
+            );
+        }
+
+        response
+    }
+}
@@ -32,12 +32,13 @@
 //! ```
 //!
 //! [vitejs]: https://vitejs.dev
-use crate::Inertia;
+use crate::{Inertia, LayoutFuture, LayoutResolver};
 use hex::encode;
 use maud::{html, PreEscaped};
 use serde::Deserialize;
 use sha1::{Digest, Sha1};
 use std::collections::HashMap;
+use std::time::Duration;
 
 pub struct Development {
     port: u16,
@@ -79,24 +80,25 @@ impl Development {
     }
 
     pub fn into_inertia(self) -> Inertia {
-        let layout = Box::new(move |props| {
+        let layout: LayoutResolver = Box::new(move |props, nonce| {
             let vite_src = format!("http://localhost:{}/@vite/client", self.port);
             let main_src = format!("http://localhost:{}/{}", self.port, self.main);
-            html! {
+            let html = html! {
                 html lang=(self.lang) {
                     head {
                         title { (self.title) }
                         meta charset="utf-8";
                         meta name="viewport" content="width=device-width, initial-scale=1.0";
-                        script type="module" src=(vite_src) {}
-                        script type="module" src=(main_src) {}
+                        script type="module" nonce=(nonce) src=(vite_src) {}
+                        script type="module" nonce=(nonce) src=(main_src) {}
                     }
                     body {
                         div #app data-page=(props) {}
                     }
                 }
             }
-            .into_string()
+            .into_string();
+            Box::pin(async move { html }) as LayoutFuture
         });
         Inertia::new(None, layout)
     }
@@ -104,7 +106,7 @@ impl Development {
 
 pub struct Production {
     main: String,
-    css: Option<String>,
+    css: Option<Vec<String>>,
     title: &'static str,
     lang: &'static str,
     /// SHA1 hash of the contents of the manifest file.
@@ -116,27 +118,9 @@ impl Production {
         manifest_path: &'static str,
         main: &'static str,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let bytes = std::fs::read(manifest_path)?;
-        let manifest: HashMap<String, ManifestEntry> =
-            serde_json::from_str(&String::from_utf8(bytes.clone())?)?;
-        let entry = manifest.get(main).ok_or(ViteError::EntryMissing(main))?;
-        let mut hasher = Sha1::new();
-        hasher.update(&bytes);
-        let result = hasher.finalize();
-        let version = encode(result);
-        let css = {
-            if let Some(css_sources) = &entry.css {
-                let mut css = String::new();
-                for source in css_sources {
-                    css.push_str(&format!(r#"<link rel="stylesheet" href="/{source}"/>"#));
-                }
-                Some(css)
-            } else {
-                None
-            }
-        };
+        let (main, css, version) = read_manifest(manifest_path, main)?;
         Ok(Self {
-            main: format!("/{}", entry.file),
+            main,
             css,
             title: "Vite",
             lang: "en",
@@ -155,28 +139,182 @@ impl Production {
     }
 
     pub fn into_inertia(self) -> Inertia {
-        let layout = Box::new(move |props| {
-            let css = self.css.clone().unwrap_or("".to_string());
-            html! {
+        let layout: LayoutResolver = Box::new(move |props, nonce| {
+            let css = self.css.clone().unwrap_or_default();
+            let html = html! {
                 html lang=(self.lang) {
                     head {
                         title { (self.title) }
                         meta charset="utf-8";
                         meta name="viewport" content="width=device-width, initial-scale=1.0";
-                        script type="module" src=(self.main) {}
-                        (PreEscaped(css))
+                        script type="module" nonce=(nonce) src=(self.main) {}
+                        @for source in &css {
+                            link rel="stylesheet" nonce=(nonce) href=(format!("/{source}"));
+                        }
                     }
                     body {
                         div #app data-page=(props) {}
                     }
                 }
             }
-            .into_string()
+            .into_string();
+            Box::pin(async move { html }) as LayoutFuture
         });
         Inertia::new(Some(self.version), layout)
     }
 }
 
+/// Configuration for Inertia backed by an external Node SSR server,
+/// e.g. one started with `inertia start-ssr`.
+///
+/// Rather than shipping an empty `<div id="app">` for the client to
+/// hydrate, the initial document is rendered by POSTing the page to
+/// the SSR server and splicing its response into the document. If
+/// the server is unreachable, or its response can't be parsed, this
+/// degrades gracefully to the same client-only document that
+/// [Production] would have served.
+pub struct Ssr {
+    url: String,
+    main: String,
+    css: Option<Vec<String>>,
+    title: &'static str,
+    lang: &'static str,
+    version: String,
+    /// Reused across every request so pages share the SSR server's
+    /// connection pool instead of paying connection setup on each
+    /// render.
+    client: reqwest::Client,
+}
+
+impl Ssr {
+    /// The URL `inertia start-ssr` listens on by default.
+    pub const DEFAULT_URL: &'static str = "http://127.0.0.1:13714/render";
+
+    pub fn new(
+        manifest_path: &'static str,
+        main: &'static str,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (main, css, version) = read_manifest(manifest_path, main)?;
+        let client = reqwest::Client::builder().timeout(SSR_TIMEOUT).build()?;
+        Ok(Self {
+            url: Self::DEFAULT_URL.to_string(),
+            main,
+            css,
+            title: "Vite",
+            lang: "en",
+            version,
+            client,
+        })
+    }
+
+    /// Overrides the SSR server's render endpoint.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    pub fn lang(mut self, lang: &'static str) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    pub fn title(mut self, title: &'static str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn client_only_document(&self, props: &str, nonce: &str) -> String {
+        let css = self.css.clone().unwrap_or_default();
+        html! {
+            html lang=(self.lang) {
+                head {
+                    title { (self.title) }
+                    meta charset="utf-8";
+                    meta name="viewport" content="width=device-width, initial-scale=1.0";
+                    script type="module" nonce=(nonce) src=(self.main) {}
+                    @for source in &css {
+                        link rel="stylesheet" nonce=(nonce) href=(format!("/{source}"));
+                    }
+                }
+                body {
+                    div #app data-page=(props) {}
+                }
+            }
+        }
+        .into_string()
+    }
+
+    pub fn into_inertia(self) -> Inertia {
+        let version = self.version.clone();
+        let layout: LayoutResolver = Box::new(move |props, nonce| {
+            let fallback = self.client_only_document(&props, &nonce);
+            let client = self.client.clone();
+            let url = self.url.clone();
+            let main = self.main.clone();
+            let css = self.css.clone().unwrap_or_default();
+            let title = self.title;
+            let lang = self.lang;
+            Box::pin(async move {
+                match render_via_ssr(&client, &url, &props).await {
+                    Ok(ssr) => html! {
+                        html lang=(lang) {
+                            head {
+                                title { (title) }
+                                meta charset="utf-8";
+                                meta name="viewport" content="width=device-width, initial-scale=1.0";
+                                script type="module" nonce=(nonce) src=(main) {}
+                                @for source in &css {
+                                    link rel="stylesheet" nonce=(nonce) href=(format!("/{source}"));
+                                }
+                                (PreEscaped(ssr.head.join("")))
+                            }
+                            body {
+                                (PreEscaped(ssr.body))
+                            }
+                        }
+                    }
+                    .into_string(),
+                    Err(_) => fallback,
+                }
+            }) as LayoutFuture
+        });
+        Inertia::new(Some(version), layout)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SsrResponse {
+    head: Vec<String>,
+    body: String,
+}
+
+/// How long to wait on the SSR server before giving up and falling
+/// back to the client-only document. Bounds a *hung* server (process
+/// alive, never responding) the same way a refused connection already
+/// fails fast on its own, so a missing or wedged SSR server never
+/// blocks the request indefinitely.
+const SSR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// POSTs the serialized `Page` JSON to the SSR server and parses its
+/// `{ head, body }` response. `client` is built once in [Ssr::new] and
+/// reused across requests so the SSR server's connection pool (and
+/// the timeout configured on it) carries over between renders.
+async fn render_via_ssr(
+    client: &reqwest::Client,
+    url: &str,
+    page_json: &str,
+) -> Result<SsrResponse, reqwest::Error> {
+    client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(page_json.to_string())
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<SsrResponse>()
+        .await
+}
+
 #[derive(Debug)]
 pub enum ViteError {
     ManifestMissing(std::io::Error),
@@ -206,3 +344,311 @@ struct ManifestEntry {
     file: String,
     css: Option<Vec<String>>,
 }
+
+/// The compiled entry's src path, its CSS sources, and the manifest's
+/// version hash, as returned by [read_manifest].
+type ManifestInfo = (String, Option<Vec<String>>, String);
+
+/// Reads a Vite manifest, looks up `main`'s compiled entry, and hashes
+/// the manifest bytes to use as the asset version. Shared by
+/// [Production::new] and [Ssr::new], which differ only in what they
+/// build around the manifest.
+fn read_manifest(
+    manifest_path: &'static str,
+    main: &'static str,
+) -> Result<ManifestInfo, Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(manifest_path)?;
+    let manifest: HashMap<String, ManifestEntry> =
+        serde_json::from_str(&String::from_utf8(bytes.clone())?)?;
+    let entry = manifest.get(main).ok_or(ViteError::EntryMissing(main))?;
+    let mut hasher = Sha1::new();
+    hasher.update(&bytes);
+    let version = encode(hasher.finalize());
+    Ok((format!("/{}", entry.file), entry.css.clone(), version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        response::IntoResponse,
+        routing::{get, post},
+        Json, Router, Server,
+    };
+    use serde_json::json;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// Extracts the CSP header's nonce and asserts it's the only nonce
+    /// on the page: every `<script>`/`<link rel="stylesheet">` tag
+    /// carries it, and there's at least one such tag to check.
+    fn assert_nonce_on_every_tag(csp: &str, body: &str) {
+        let nonce = csp
+            .strip_prefix("script-src 'nonce-")
+            .and_then(|s| s.strip_suffix("'"))
+            .expect("CSP header should be `script-src 'nonce-...'`");
+
+        let tag_count = body.matches("<script").count() + body.matches("<link").count();
+        assert!(tag_count > 0, "expected at least one script/link tag");
+        assert_eq!(
+            body.matches(&format!(r#"nonce="{nonce}""#)).count(),
+            tag_count,
+            "every <script>/<link> tag should carry the CSP nonce"
+        );
+    }
+
+    /// Writes a throwaway manifest naming `main` and returns its path,
+    /// leaked to satisfy [Ssr::new]'s `&'static str` manifest path.
+    fn write_manifest(main: &str) -> &'static str {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "axum-inertia-ssr-test-manifest-{}-{id}.json",
+            std::process::id()
+        ));
+        let mut file = std::fs::File::create(&path).unwrap();
+        write!(
+            file,
+            r#"{{"{main}": {{"file": "assets/main.js", "css": []}}}}"#
+        )
+        .unwrap();
+        Box::leak(path.into_os_string().into_string().unwrap().into_boxed_str())
+    }
+
+    #[tokio::test]
+    async fn it_stamps_the_csp_nonce_on_every_development_tag() {
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let development = Development::default().port(5173).main("src/main.ts");
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(development.into_inertia());
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .expect("server error");
+        });
+
+        let res = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap();
+        let csp = res
+            .headers()
+            .get("Content-Security-Policy")
+            .map(|h| h.to_str().unwrap())
+            .unwrap()
+            .to_string();
+        let body = res.text().await.unwrap();
+
+        assert_nonce_on_every_tag(&csp, &body);
+    }
+
+    #[tokio::test]
+    async fn it_stamps_the_csp_nonce_on_every_production_tag() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "axum-inertia-production-test-manifest-{}-{id}.json",
+            std::process::id()
+        ));
+        let mut file = std::fs::File::create(&path).unwrap();
+        write!(
+            file,
+            r#"{{"src/main.ts": {{"file": "assets/main.js", "css": ["assets/main.css"]}}}}"#
+        )
+        .unwrap();
+        let manifest_path: &'static str =
+            Box::leak(path.into_os_string().into_string().unwrap().into_boxed_str());
+
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let production = Production::new(manifest_path, "src/main.ts").unwrap();
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(production.into_inertia());
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .expect("server error");
+        });
+
+        let res = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap();
+        let csp = res
+            .headers()
+            .get("Content-Security-Policy")
+            .map(|h| h.to_str().unwrap())
+            .unwrap()
+            .to_string();
+        let body = res.text().await.unwrap();
+
+        assert!(body.contains(r#"link rel="stylesheet""#));
+        assert_nonce_on_every_tag(&csp, &body);
+    }
+
+    #[tokio::test]
+    async fn it_splices_the_ssr_response_into_the_document() {
+        async fn ssr_handler(body: String) -> Json<serde_json::Value> {
+            assert!(body.contains("\"bar\""));
+            Json(json!({
+                "head": ["<meta name=\"ssr\" content=\"1\">"],
+                "body": "<div id=\"app\">rendered on the server</div>",
+            }))
+        }
+
+        let ssr_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ssr_addr = ssr_listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let app = Router::new().route("/render", post(ssr_handler));
+            Server::from_tcp(ssr_listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .unwrap();
+        });
+
+        let manifest_path = write_manifest("src/main.ts");
+        let ssr = Ssr::new(manifest_path, "src/main.ts")
+            .unwrap()
+            .url(format!("http://{}/render", ssr_addr));
+
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(ssr.into_inertia());
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .expect("server error");
+        });
+
+        let body = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        assert!(body.contains(r#"<meta name="ssr" content="1">"#));
+        assert!(body.contains("rendered on the server"));
+    }
+
+    #[tokio::test]
+    async fn it_falls_back_to_the_client_only_document_when_the_ssr_server_is_unreachable() {
+        let manifest_path = write_manifest("src/main.ts");
+        let ssr = Ssr::new(manifest_path, "src/main.ts")
+            .unwrap()
+            // Nothing listens here, so the connection is refused
+            // immediately instead of exercising the timeout.
+            .url("http://127.0.0.1:1/render");
+
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(ssr.into_inertia());
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .expect("server error");
+        });
+
+        let res = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+        let body = res.text().await.unwrap();
+        assert!(body.contains(r#"div id="app""#));
+        assert!(!body.contains("rendered on the server"));
+    }
+
+    #[tokio::test]
+    async fn it_falls_back_after_the_ssr_request_times_out() {
+        let manifest_path = write_manifest("src/main.ts");
+        let mut ssr = Ssr::new(manifest_path, "src/main.ts").unwrap();
+        // Swap in a short timeout so the test doesn't have to wait out
+        // the real SSR_TIMEOUT to exercise this path.
+        ssr.client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let ssr_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ssr_addr = ssr_listener.local_addr().unwrap();
+        // Accepts the connection but never writes a response, so it's
+        // the client's timeout that triggers the fallback here, not a
+        // refused connection.
+        std::thread::spawn(move || {
+            let _stream = ssr_listener.accept().unwrap();
+            std::thread::sleep(Duration::from_secs(10));
+        });
+
+        let ssr = ssr.url(format!("http://{}/render", ssr_addr));
+
+        async fn handler(i: Inertia) -> impl IntoResponse {
+            i.render("foo!", json!({"bar": "baz"})).await
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_state(ssr.into_inertia());
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind ephemeral socket");
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service())
+                .await
+                .expect("server error");
+        });
+
+        let res = reqwest::get(format!("http://{}/test", &addr))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+        let body = res.text().await.unwrap();
+        assert!(body.contains(r#"div id="app""#));
+        assert!(!body.contains("rendered on the server"));
+    }
+}